@@ -4,7 +4,7 @@ use crate::connection::AxisScale;
 use crate::estimate::Statistic;
 use crate::kde;
 use crate::model::Benchmark;
-use crate::report::{BenchmarkId, ValueType};
+use crate::report::{BenchmarkId, Throughput, ValueType};
 use crate::stats::univariate::Sample;
 use crate::value_formatter::ValueFormatter;
 use criterion_plot::prelude::*;
@@ -25,6 +25,8 @@ static COMPARISON_COLORS: [Color; NUM_COLORS] = [
     Color::Rgb(0, 255, 127),
 ];
 
+const BASELINE_COLOR: Color = Color::Rgb(178, 34, 34);
+
 impl AxisScale {
     fn to_gnuplot(self) -> Scale {
         match self {
@@ -34,7 +36,14 @@ impl AxisScale {
     }
 }
 
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::explicit_counter_loop))]
+/// Selects what the line comparison chart plots on the Y axis: average time
+/// (`line_comparison`) or throughput (`line_comparison_throughput`).
+#[derive(Clone, Copy)]
+pub enum LinePlotConfig {
+    Time,
+    Throughput,
+}
+
 pub fn line_comparison(
     formatter: &dyn ValueFormatter,
     title: &str,
@@ -42,6 +51,48 @@ pub fn line_comparison(
     path: &Path,
     value_type: ValueType,
     axis_scale: AxisScale,
+) -> Child {
+    line_comparison_figure(
+        Some(formatter),
+        title,
+        all_benchmarks,
+        path,
+        value_type,
+        axis_scale,
+        LinePlotConfig::Time,
+    )
+}
+
+/// Like `line_comparison`, but plots each benchmark's throughput instead of its average time.
+/// A function's line is left out of the plot unless every benchmark in its group has a
+/// `Throughput` of the same variant.
+pub fn line_comparison_throughput(
+    title: &str,
+    all_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    value_type: ValueType,
+    axis_scale: AxisScale,
+) -> Child {
+    line_comparison_figure(
+        None,
+        title,
+        all_benchmarks,
+        path,
+        value_type,
+        axis_scale,
+        LinePlotConfig::Throughput,
+    )
+}
+
+#[cfg_attr(feature = "cargo-clippy", allow(clippy::explicit_counter_loop))]
+fn line_comparison_figure(
+    formatter: Option<&dyn ValueFormatter>,
+    title: &str,
+    all_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    value_type: ValueType,
+    axis_scale: AxisScale,
+    config: LinePlotConfig,
 ) -> Child {
     let path = PathBuf::from(path);
     let mut f = Figure::new();
@@ -65,29 +116,6 @@ pub fn line_comparison(
                 .set(axis_scale.to_gnuplot())
         });
 
-    let mut i = 0;
-
-    let max = all_benchmarks
-        .iter()
-        .map(|(_, ref data)| {
-            data.latest_stats
-                .estimates
-                .get(&Statistic::Typical)
-                .unwrap()
-                .point_estimate
-        })
-        .fold(::std::f64::NAN, f64::max);
-
-    let mut dummy = [1.0];
-    let unit = formatter.scale_values(max, &mut dummy);
-
-    f.configure(Axis::LeftY, |a| {
-        a.configure(Grid::Major, |g| g.show())
-            .configure(Grid::Minor, |g| g.hide())
-            .set(Label(format!("Average time ({})", unit)))
-            .set(axis_scale.to_gnuplot())
-    });
-
     let mut function_id_to_benchmarks = LinkedHashMap::new();
     for (id, bench) in all_benchmarks {
         function_id_to_benchmarks
@@ -96,49 +124,167 @@ pub fn line_comparison(
             .push((*id, *bench))
     }
 
-    for (key, mut group) in function_id_to_benchmarks {
-        // Unwrap is fine here because the caller shouldn't call this with non-numeric IDs.
-        let mut tuples: Vec<_> = group
-            .into_iter()
-            .map(|(id, benchmark)| {
-                let x = id.as_number().unwrap();
-                let y = benchmark
-                    .latest_stats
-                    .estimates
-                    .get(&Statistic::Typical)
-                    .unwrap()
-                    .point_estimate;
-
-                (x, y)
-            })
-            .collect();
-        tuples.sort_by(|&(ax, _), &(bx, _)| (ax.partial_cmp(&bx).unwrap_or(Ordering::Less)));
-        let (xs, mut ys): (Vec<_>, Vec<_>) = tuples.into_iter().unzip();
-        formatter.scale_values(max, &mut ys);
-
-        let function_name = key.as_ref().map(|string| escape_underscores(string));
-
-        f.plot(Lines { x: &xs, y: &ys }, |c| {
-            if let Some(name) = function_name {
-                c.set(Label(name));
+    let mut i = 0;
+    match config {
+        LinePlotConfig::Time => {
+            let formatter = formatter.expect("a formatter is required to plot time");
+            let max = all_benchmarks
+                .iter()
+                .map(|(_, ref data)| {
+                    data.latest_stats
+                        .estimates
+                        .get(&Statistic::Typical)
+                        .unwrap()
+                        .point_estimate
+                })
+                .fold(::std::f64::NAN, f64::max);
+
+            let mut dummy = [1.0];
+            let unit = formatter.scale_values(max, &mut dummy);
+
+            f.configure(Axis::LeftY, |a| {
+                a.configure(Grid::Major, |g| g.show())
+                    .configure(Grid::Minor, |g| g.hide())
+                    .set(Label(format!("Average time ({})", unit)))
+                    .set(axis_scale.to_gnuplot())
+            });
+
+            for (key, group) in function_id_to_benchmarks {
+                // Unwrap is fine here because the caller shouldn't call this with non-numeric IDs.
+                let mut tuples: Vec<_> = group
+                    .into_iter()
+                    .map(|(id, benchmark)| {
+                        let x = id.as_number().unwrap();
+                        let estimate = benchmark
+                            .latest_stats
+                            .estimates
+                            .get(&Statistic::Typical)
+                            .unwrap();
+
+                        (
+                            x,
+                            estimate.point_estimate,
+                            estimate.confidence_interval.lower_bound,
+                            estimate.confidence_interval.upper_bound,
+                        )
+                    })
+                    .collect();
+                tuples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Less));
+
+                let xs: Vec<_> = tuples.iter().map(|t| t.0).collect();
+                let mut ys: Vec<_> = tuples.iter().map(|t| t.1).collect();
+                let mut lower: Vec<_> = tuples.iter().map(|t| t.2).collect();
+                let mut upper: Vec<_> = tuples.iter().map(|t| t.3).collect();
+                formatter.scale_values(max, &mut ys);
+                formatter.scale_values(max, &mut lower);
+                formatter.scale_values(max, &mut upper);
+
+                // The confidence band is drawn before the center line so the line and its
+                // points are never hidden behind the fill.
+                f.plot(
+                    FilledCurve {
+                        x: xs.clone(),
+                        y1: upper,
+                        y2: lower,
+                    },
+                    |c| c.set(COMPARISON_COLORS[i % NUM_COLORS]).set(Opacity(0.25)),
+                );
+
+                plot_comparison_line(&mut f, key, &xs, &ys, i);
+                i += 1;
             }
-            c.set(LINEWIDTH)
-                .set(LineType::Solid)
-                .set(COMPARISON_COLORS[i % NUM_COLORS])
-        })
-        .plot(Points { x: &xs, y: &ys }, |p| {
-            p.set(PointType::FilledCircle)
-                .set(POINT_SIZE)
-                .set(COMPARISON_COLORS[i % NUM_COLORS])
-        });
+        }
+        LinePlotConfig::Throughput => {
+            let y_label = function_id_to_benchmarks
+                .values()
+                .find_map(|group| throughput_unit(group))
+                .map(|unit| format!("Throughput ({})", unit))
+                .unwrap_or_else(|| "Throughput".to_string());
+
+            f.configure(Axis::LeftY, |a| {
+                a.configure(Grid::Major, |g| g.show())
+                    .configure(Grid::Minor, |g| g.hide())
+                    .set(Label(y_label))
+                    .set(axis_scale.to_gnuplot())
+            });
+
+            for (key, group) in function_id_to_benchmarks {
+                // Skip function groups whose benchmarks don't share a single Throughput variant.
+                if throughput_unit(&group).is_none() {
+                    continue;
+                }
+
+                let mut tuples: Vec<_> = group
+                    .into_iter()
+                    .map(|(id, benchmark)| {
+                        let x = id.as_number().unwrap();
+                        let seconds = benchmark
+                            .latest_stats
+                            .estimates
+                            .get(&Statistic::Typical)
+                            .unwrap()
+                            .point_estimate;
+                        let y = throughput_amount(id.throughput.as_ref().unwrap()) / seconds;
 
-        i += 1;
+                        (x, y)
+                    })
+                    .collect();
+                tuples
+                    .sort_by(|&(ax, _), &(bx, _)| (ax.partial_cmp(&bx).unwrap_or(Ordering::Less)));
+                let (xs, ys): (Vec<_>, Vec<_>) = tuples.into_iter().unzip();
+
+                plot_comparison_line(&mut f, key, &xs, &ys, i);
+                i += 1;
+            }
+        }
     }
 
     debug_script(&path, &f);
     f.set(Output(path)).draw().unwrap()
 }
 
+fn plot_comparison_line(f: &mut Figure, key: &Option<String>, xs: &[f64], ys: &[f64], i: usize) {
+    let function_name = key.as_ref().map(|string| escape_underscores(string));
+
+    f.plot(Lines { x: xs, y: ys }, |c| {
+        if let Some(name) = function_name {
+            c.set(Label(name));
+        }
+        c.set(LINEWIDTH)
+            .set(LineType::Solid)
+            .set(COMPARISON_COLORS[i % NUM_COLORS])
+    })
+    .plot(Points { x: xs, y: ys }, |p| {
+        p.set(PointType::FilledCircle)
+            .set(POINT_SIZE)
+            .set(COMPARISON_COLORS[i % NUM_COLORS])
+    });
+}
+
+fn throughput_amount(throughput: &Throughput) -> f64 {
+    match *throughput {
+        Throughput::Bytes(n) | Throughput::Elements(n) => n as f64,
+    }
+}
+
+/// Returns the shared throughput unit ("bytes/s" or "elements/s") for a function's group of
+/// benchmarks, or `None` if any benchmark lacks a `Throughput` or the group mixes variants.
+fn throughput_unit(group: &[(&BenchmarkId, &Benchmark)]) -> Option<&'static str> {
+    let mut unit = None;
+    for (id, _) in group {
+        let label = match id.throughput.as_ref()? {
+            Throughput::Bytes(_) => "bytes/s",
+            Throughput::Elements(_) => "elements/s",
+        };
+        match unit {
+            None => unit = Some(label),
+            Some(existing) if existing == label => {}
+            Some(_) => return None,
+        }
+    }
+    unit
+}
+
 pub fn violin(
     formatter: &dyn ValueFormatter,
     title: &str,
@@ -151,19 +297,7 @@ pub fn violin(
     let kdes = all_benchmarks
         .iter()
         .rev()
-        .map(|(_, benchmark)| {
-            let (x, mut y) = kde::sweep(
-                Sample::new(&benchmark.latest_stats.avg_values),
-                KDE_POINTS,
-                None,
-            );
-            let y_max = Sample::new(&y).max();
-            for y in y.iter_mut() {
-                *y /= y_max;
-            }
-
-            (x, y)
-        })
+        .map(|(_, benchmark)| normalized_kde(&benchmark.latest_stats.avg_values))
         .collect::<Vec<_>>();
     let mut xs = kdes
         .iter()
@@ -230,4 +364,163 @@ pub fn violin(
     }
     debug_script(&path, &f);
     f.set(Output(path)).draw().unwrap()
-}
\ No newline at end of file
+}
+
+fn normalized_kde(avg_values: &[f64]) -> (Box<[f64]>, Box<[f64]>) {
+    let (x, mut y) = kde::sweep(Sample::new(avg_values), KDE_POINTS, None);
+    let y_max = Sample::new(&y).max();
+    for y in y.iter_mut() {
+        *y /= y_max;
+    }
+
+    (x, y)
+}
+
+struct ComparisonRow<'a> {
+    id: &'a BenchmarkId,
+    current_x: Box<[f64]>,
+    current_y: Box<[f64]>,
+    current_mean: f64,
+    baseline_x: Box<[f64]>,
+    baseline_y: Box<[f64]>,
+    baseline_mean: f64,
+}
+
+/// Like `violin`, but overlays each benchmark's current distribution on top of its saved
+/// baseline so regressions show up as a shift in shape rather than a single number. Benchmarks
+/// in `current_benchmarks` and `baseline_benchmarks` are paired up by position.
+pub fn violin_comparison(
+    formatter: &dyn ValueFormatter,
+    title: &str,
+    current_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    baseline_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    axis_scale: AxisScale,
+) -> Child {
+    let path = PathBuf::from(&path);
+
+    let rows: Vec<_> = current_benchmarks
+        .iter()
+        .zip(baseline_benchmarks.iter())
+        .rev()
+        .map(|((id, current), (_, baseline))| {
+            let (current_x, current_y) = normalized_kde(&current.latest_stats.avg_values);
+            let (baseline_x, baseline_y) = normalized_kde(&baseline.latest_stats.avg_values);
+            let current_mean = Sample::new(&current.latest_stats.avg_values).mean();
+            let baseline_mean = Sample::new(&baseline.latest_stats.avg_values).mean();
+
+            ComparisonRow {
+                id: *id,
+                current_x,
+                current_y,
+                current_mean,
+                baseline_x,
+                baseline_y,
+                baseline_mean,
+            }
+        })
+        .collect();
+
+    let typical = rows
+        .iter()
+        .flat_map(|row| row.current_x.iter().chain(row.baseline_x.iter()))
+        .cloned()
+        .filter(|&x| x > 0.)
+        .fold(::std::f64::NAN, f64::max);
+
+    let mut one = [1.0];
+    let unit = formatter.scale_values(typical, &mut one);
+
+    let tics = || (0..).map(|x| (f64::from(x)) + 0.5);
+    let size = Size(1280, 200 + (25 * rows.len()));
+    let mut f = Figure::new();
+    f.set(Font(DEFAULT_FONT))
+        .set(size)
+        .set(Title(format!(
+            "{}: Violin plot (baseline vs. current)",
+            escape_underscores(title)
+        )))
+        .configure(Axis::BottomX, |a| {
+            a.configure(Grid::Major, |g| g.show())
+                .configure(Grid::Minor, |g| g.hide())
+                .set(Label(format!("Average time ({})", unit)))
+                .set(axis_scale.to_gnuplot())
+        })
+        .configure(Axis::LeftY, |a| {
+            a.set(Label("Input"))
+                .set(Range::Limits(0., rows.len() as f64))
+                .set(TicLabels {
+                    positions: tics(),
+                    labels: rows.iter().map(|row| escape_underscores(row.id.as_title())),
+                })
+        });
+
+    let mut is_first = true;
+    for (i, row) in rows.iter().enumerate() {
+        let i = i as f64 + 0.5;
+
+        let baseline_y1: Vec<_> = row.baseline_y.iter().map(|&y| i + y * 0.45).collect();
+        let baseline_y2: Vec<_> = row.baseline_y.iter().map(|&y| i - y * 0.45).collect();
+        let baseline_x: Vec<_> = row.baseline_x.iter().map(|&x| x * one[0]).collect();
+
+        f.plot(
+            FilledCurve {
+                x: baseline_x,
+                y1: baseline_y1,
+                y2: baseline_y2,
+            },
+            |c| {
+                if is_first {
+                    c.set(BASELINE_COLOR)
+                        .set(Label("Baseline"))
+                        .set(Opacity(0.25))
+                } else {
+                    c.set(BASELINE_COLOR).set(Opacity(0.25))
+                }
+            },
+        );
+
+        let current_y1: Vec<_> = row.current_y.iter().map(|&y| i + y * 0.45).collect();
+        let current_y2: Vec<_> = row.current_y.iter().map(|&y| i - y * 0.45).collect();
+        let current_x: Vec<_> = row.current_x.iter().map(|&x| x * one[0]).collect();
+
+        f.plot(
+            FilledCurve {
+                x: current_x,
+                y1: current_y1,
+                y2: current_y2,
+            },
+            |c| {
+                if is_first {
+                    is_first = false;
+
+                    c.set(DARK_BLUE).set(Label("Current")).set(Opacity(0.25))
+                } else {
+                    c.set(DARK_BLUE).set(Opacity(0.25))
+                }
+            },
+        );
+
+        let mean_y_span = vec![i - 0.45, i + 0.45];
+
+        let baseline_mean = vec![row.baseline_mean * one[0]; 2];
+        f.plot(
+            Lines {
+                x: &baseline_mean,
+                y: &mean_y_span,
+            },
+            |l| l.set(BASELINE_COLOR).set(LineType::Solid).set(LINEWIDTH),
+        );
+
+        let current_mean = vec![row.current_mean * one[0]; 2];
+        f.plot(
+            Lines {
+                x: &current_mean,
+                y: &mean_y_span,
+            },
+            |l| l.set(DARK_BLUE).set(LineType::Solid).set(LINEWIDTH),
+        );
+    }
+    debug_script(&path, &f);
+    f.set(Output(path)).draw().unwrap()
+}
@@ -2,6 +2,7 @@ use super::*;
 use crate::connection::AxisScale;
 use crate::estimate::Statistic;
 use crate::model::Benchmark;
+use crate::report::Throughput;
 use linked_hash_map::LinkedHashMap;
 use plotters::coord::{AsRangedCoord, Shift};
 use std::cmp::Ordering;
@@ -19,6 +20,16 @@ static COMPARISON_COLORS: [RGBColor; NUM_COLORS] = [
     RGBColor(0, 255, 127),
 ];
 
+const BASELINE_COLOR: RGBColor = RGBColor(178, 34, 34);
+
+/// Selects what the line comparison chart plots on the Y axis: average time
+/// (`line_comparison`) or throughput (`line_comparison_throughput`).
+#[derive(Clone, Copy)]
+pub enum LinePlotConfig {
+    Time,
+    Throughput,
+}
+
 pub fn line_comparison(
     formatter: &dyn ValueFormatter,
     title: &str,
@@ -27,12 +38,59 @@ pub fn line_comparison(
     value_type: ValueType,
     axis_scale: AxisScale,
 ) {
-    let (unit, series_data) = line_comparison_series_data(formatter, all_curves);
+    line_comparison_figure(
+        Some(formatter),
+        title,
+        all_curves,
+        path,
+        value_type,
+        axis_scale,
+        LinePlotConfig::Time,
+    )
+}
+
+/// Like `line_comparison`, but plots each benchmark's throughput instead of its average time.
+/// A function's line is left out of the plot unless every benchmark in its group has a
+/// `Throughput` of the same variant.
+pub fn line_comparison_throughput(
+    title: &str,
+    all_curves: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    value_type: ValueType,
+    axis_scale: AxisScale,
+) {
+    line_comparison_figure(
+        None,
+        title,
+        all_curves,
+        path,
+        value_type,
+        axis_scale,
+        LinePlotConfig::Throughput,
+    )
+}
+
+fn line_comparison_figure(
+    formatter: Option<&dyn ValueFormatter>,
+    title: &str,
+    all_curves: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    value_type: ValueType,
+    axis_scale: AxisScale,
+    config: LinePlotConfig,
+) {
+    let (y_desc, series_data) = line_comparison_series_data(formatter, all_curves, config);
 
     let x_range =
-        plotters::data::fitting_range(series_data.iter().map(|(_, xs, _)| xs.iter()).flatten());
-    let y_range =
-        plotters::data::fitting_range(series_data.iter().map(|(_, _, ys)| ys.iter()).flatten());
+        plotters::data::fitting_range(series_data.iter().map(|(_, xs, _, _)| xs.iter()).flatten());
+    let y_range = plotters::data::fitting_range(series_data.iter().flat_map(
+        |(_, _, ys, bounds)| -> Box<dyn Iterator<Item = &f64>> {
+            match bounds {
+                Some((lower, upper)) => Box::new(ys.iter().chain(lower.iter()).chain(upper.iter())),
+                None => Box::new(ys.iter()),
+            }
+        },
+    ));
     let root_area = SVGBackend::new(&path, SIZE)
         .into_drawing_area()
         .titled(&format!("{}: Comparision", title), (DEFAULT_FONT, 20))
@@ -41,7 +99,7 @@ pub fn line_comparison(
     match axis_scale {
         AxisScale::Linear => draw_line_comparision_figure(
             root_area,
-            &unit,
+            &y_desc,
             x_range,
             y_range,
             value_type,
@@ -49,7 +107,7 @@ pub fn line_comparison(
         ),
         AxisScale::Logarithmic => draw_line_comparision_figure(
             root_area,
-            &unit,
+            &y_desc,
             LogRange(x_range),
             LogRange(y_range),
             value_type,
@@ -58,13 +116,19 @@ pub fn line_comparison(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn draw_line_comparision_figure<XR: AsRangedCoord<Value = f64>, YR: AsRangedCoord<Value = f64>>(
     root_area: DrawingArea<SVGBackend, Shift>,
-    y_unit: &str,
+    y_desc: &str,
     x_range: XR,
     y_range: YR,
     value_type: ValueType,
-    data: Vec<(Option<&String>, Vec<f64>, Vec<f64>)>,
+    data: Vec<(
+        Option<&String>,
+        Vec<f64>,
+        Vec<f64>,
+        Option<(Vec<f64>, Vec<f64>)>,
+    )>,
 ) {
     let input_suffix = match value_type {
         ValueType::Bytes => " Size (Bytes)",
@@ -83,11 +147,33 @@ fn draw_line_comparision_figure<XR: AsRangedCoord<Value = f64>, YR: AsRangedCoor
         .configure_mesh()
         .disable_mesh()
         .x_desc(format!("Input{}", input_suffix))
-        .y_desc(format!("Average time ({})", y_unit))
+        .y_desc(y_desc)
         .draw()
         .unwrap();
 
-    for (id, (name, xs, ys)) in (0..).zip(data.into_iter()) {
+    for (id, (name, xs, ys, bounds)) in (0..).zip(data.into_iter()) {
+        // The confidence band is drawn before the center line so the line and its points are
+        // never hidden behind the fill.
+        if let Some((lower, upper)) = bounds {
+            let band: Vec<_> = xs
+                .iter()
+                .zip(upper.iter())
+                .map(|(&x, &y)| (x, y))
+                .chain(
+                    xs.iter()
+                        .rev()
+                        .zip(lower.iter().rev())
+                        .map(|(&x, &y)| (x, y)),
+                )
+                .collect();
+            chart
+                .draw_series(std::iter::once(Polygon::new(
+                    band,
+                    &COMPARISON_COLORS[id % NUM_COLORS].mix(0.25),
+                )))
+                .unwrap();
+        }
+
         let series = chart
             .draw_series(
                 LineSeries::new(
@@ -117,26 +203,18 @@ fn draw_line_comparision_figure<XR: AsRangedCoord<Value = f64>, YR: AsRangedCoor
 
 #[allow(clippy::type_complexity)]
 fn line_comparison_series_data<'a>(
-    formatter: &dyn ValueFormatter,
+    formatter: Option<&dyn ValueFormatter>,
     all_benchmarks: &[(&'a BenchmarkId, &'a Benchmark)],
-) -> (String, Vec<(Option<&'a String>, Vec<f64>, Vec<f64>)>) {
-    let max = all_benchmarks
-        .iter()
-        .map(|(_, bench)| {
-            bench
-                .latest_stats
-                .estimates
-                .get(&Statistic::Typical)
-                .unwrap()
-                .point_estimate
-        })
-        .fold(::std::f64::NAN, f64::max);
-
-    let mut dummy = [1.0];
-    let unit = formatter.scale_values(max, &mut dummy);
-
-    let mut series_data = vec![];
-
+    config: LinePlotConfig,
+) -> (
+    String,
+    Vec<(
+        Option<&'a String>,
+        Vec<f64>,
+        Vec<f64>,
+        Option<(Vec<f64>, Vec<f64>)>,
+    )>,
+) {
     let mut function_id_to_benchmarks = LinkedHashMap::new();
     for (id, bench) in all_benchmarks {
         function_id_to_benchmarks
@@ -145,29 +223,124 @@ fn line_comparison_series_data<'a>(
             .push((*id, *bench))
     }
 
-    for (key, mut group) in function_id_to_benchmarks {
-        // Unwrap is fine here because the caller shouldn't call this with non-numeric IDs.
-        let mut tuples: Vec<_> = group
-            .into_iter()
-            .map(|(id, bench)| {
-                let x = id.as_number().unwrap();
-                let y = bench
-                    .latest_stats
-                    .estimates
-                    .get(&Statistic::Typical)
-                    .unwrap()
-                    .point_estimate;
-
-                (x, y)
-            })
-            .collect();
-        tuples.sort_by(|&(ax, _), &(bx, _)| (ax.partial_cmp(&bx).unwrap_or(Ordering::Less)));
-        let function_name = key.as_ref();
-        let (xs, mut ys): (Vec<_>, Vec<_>) = tuples.into_iter().unzip();
-        formatter.scale_values(max, &mut ys);
-        series_data.push((function_name, xs, ys));
+    match config {
+        LinePlotConfig::Time => {
+            let formatter = formatter.expect("a formatter is required to plot time");
+            let max = all_benchmarks
+                .iter()
+                .map(|(_, bench)| {
+                    bench
+                        .latest_stats
+                        .estimates
+                        .get(&Statistic::Typical)
+                        .unwrap()
+                        .point_estimate
+                })
+                .fold(::std::f64::NAN, f64::max);
+
+            let mut dummy = [1.0];
+            let unit = formatter.scale_values(max, &mut dummy);
+            let y_desc = format!("Average time ({})", unit);
+
+            let mut series_data = vec![];
+            for (key, group) in function_id_to_benchmarks {
+                // Unwrap is fine here because the caller shouldn't call this with non-numeric IDs.
+                let mut tuples: Vec<_> = group
+                    .into_iter()
+                    .map(|(id, bench)| {
+                        let x = id.as_number().unwrap();
+                        let estimate = bench
+                            .latest_stats
+                            .estimates
+                            .get(&Statistic::Typical)
+                            .unwrap();
+
+                        (
+                            x,
+                            estimate.point_estimate,
+                            estimate.confidence_interval.lower_bound,
+                            estimate.confidence_interval.upper_bound,
+                        )
+                    })
+                    .collect();
+                tuples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Less));
+
+                let function_name = key.as_ref();
+                let xs: Vec<_> = tuples.iter().map(|t| t.0).collect();
+                let mut ys: Vec<_> = tuples.iter().map(|t| t.1).collect();
+                let mut lower: Vec<_> = tuples.iter().map(|t| t.2).collect();
+                let mut upper: Vec<_> = tuples.iter().map(|t| t.3).collect();
+                formatter.scale_values(max, &mut ys);
+                formatter.scale_values(max, &mut lower);
+                formatter.scale_values(max, &mut upper);
+                series_data.push((function_name, xs, ys, Some((lower, upper))));
+            }
+            (y_desc, series_data)
+        }
+        LinePlotConfig::Throughput => {
+            let mut y_desc = None;
+            let mut series_data = vec![];
+            for (key, group) in function_id_to_benchmarks {
+                // Skip function groups whose benchmarks don't share a single Throughput variant.
+                let unit = match throughput_unit(&group) {
+                    Some(unit) => unit,
+                    None => continue,
+                };
+                if y_desc.is_none() {
+                    y_desc = Some(format!("Throughput ({})", unit));
+                }
+
+                let mut tuples: Vec<_> = group
+                    .into_iter()
+                    .map(|(id, bench)| {
+                        let x = id.as_number().unwrap();
+                        let seconds = bench
+                            .latest_stats
+                            .estimates
+                            .get(&Statistic::Typical)
+                            .unwrap()
+                            .point_estimate;
+                        let y = throughput_amount(id.throughput.as_ref().unwrap()) / seconds;
+
+                        (x, y)
+                    })
+                    .collect();
+                tuples
+                    .sort_by(|&(ax, _), &(bx, _)| (ax.partial_cmp(&bx).unwrap_or(Ordering::Less)));
+                let function_name = key.as_ref();
+                let (xs, ys): (Vec<_>, Vec<_>) = tuples.into_iter().unzip();
+                series_data.push((function_name, xs, ys, None));
+            }
+            (
+                y_desc.unwrap_or_else(|| "Throughput".to_string()),
+                series_data,
+            )
+        }
     }
-    (unit, series_data)
+}
+
+fn throughput_amount(throughput: &Throughput) -> f64 {
+    match *throughput {
+        Throughput::Bytes(n) | Throughput::Elements(n) => n as f64,
+    }
+}
+
+/// Returns the shared throughput unit ("bytes/s" or "elements/s") for a function's group of
+/// benchmarks, or `None` if any benchmark lacks a `Throughput` or the group mixes variants.
+fn throughput_unit(group: &[(&BenchmarkId, &Benchmark)]) -> Option<&'static str> {
+    let mut unit = None;
+    for (id, _) in group {
+        let label = match id.throughput.as_ref()? {
+            Throughput::Bytes(_) => "bytes/s",
+            Throughput::Elements(_) => "elements/s",
+        };
+        match unit {
+            None => unit = Some(label),
+            Some(existing) if existing == label => {}
+            Some(_) => return None,
+        }
+    }
+    unit
 }
 
 pub fn violin(
@@ -181,16 +354,7 @@ pub fn violin(
         .iter()
         .rev()
         .map(|(id, sample)| {
-            let (x, mut y) = kde::sweep(
-                Sample::new(&sample.latest_stats.avg_values),
-                KDE_POINTS,
-                None,
-            );
-            let y_max = Sample::new(&y).max();
-            for y in y.iter_mut() {
-                *y /= y_max;
-            }
-
+            let (x, y) = normalized_kde(&sample.latest_stats.avg_values);
             (id.as_title(), x, y)
         })
         .collect::<Vec<_>>();
@@ -279,4 +443,197 @@ fn draw_violin_figure<XR: AsRangedCoord<Value = f64>, YR: AsRangedCoord<Value =
             ))
             .unwrap();
     }
-}
\ No newline at end of file
+}
+
+fn normalized_kde(avg_values: &[f64]) -> (Box<[f64]>, Box<[f64]>) {
+    let (x, mut y) = kde::sweep(Sample::new(avg_values), KDE_POINTS, None);
+    let y_max = Sample::new(&y).max();
+    for y in y.iter_mut() {
+        *y /= y_max;
+    }
+
+    (x, y)
+}
+
+struct ComparisonRow<'a> {
+    name: &'a str,
+    current_x: Box<[f64]>,
+    current_y: Box<[f64]>,
+    current_mean: f64,
+    baseline_x: Box<[f64]>,
+    baseline_y: Box<[f64]>,
+    baseline_mean: f64,
+}
+
+/// Like `violin`, but overlays each benchmark's current distribution on top of its saved
+/// baseline so regressions show up as a shift in shape rather than a single number. Benchmarks
+/// in `current_benchmarks` and `baseline_benchmarks` are paired up by position.
+pub fn violin_comparison(
+    formatter: &dyn ValueFormatter,
+    title: &str,
+    current_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    baseline_benchmarks: &[(&BenchmarkId, &Benchmark)],
+    path: &Path,
+    axis_scale: AxisScale,
+) {
+    let mut rows = current_benchmarks
+        .iter()
+        .zip(baseline_benchmarks.iter())
+        .rev()
+        .map(|((id, current), (_, baseline))| {
+            let (current_x, current_y) = normalized_kde(&current.latest_stats.avg_values);
+            let (baseline_x, baseline_y) = normalized_kde(&baseline.latest_stats.avg_values);
+            let current_mean = Sample::new(&current.latest_stats.avg_values).mean();
+            let baseline_mean = Sample::new(&baseline.latest_stats.avg_values).mean();
+
+            ComparisonRow {
+                name: id.as_title(),
+                current_x,
+                current_y,
+                current_mean,
+                baseline_x,
+                baseline_y,
+                baseline_mean,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let max = rows
+        .iter()
+        .flat_map(|row| row.current_x.iter().chain(row.baseline_x.iter()))
+        .cloned()
+        .filter(|&x| x > 0.)
+        .fold(::std::f64::NAN, f64::max);
+
+    let mut dummy = [1.0];
+    let unit = formatter.scale_values(max, &mut dummy);
+    for row in rows.iter_mut() {
+        formatter.scale_values(max, &mut row.current_x);
+        formatter.scale_values(max, &mut row.baseline_x);
+
+        let mut current_mean = [row.current_mean];
+        formatter.scale_values(max, &mut current_mean);
+        row.current_mean = current_mean[0];
+
+        let mut baseline_mean = [row.baseline_mean];
+        formatter.scale_values(max, &mut baseline_mean);
+        row.baseline_mean = baseline_mean[0];
+    }
+
+    let x_range = plotters::data::fitting_range(
+        rows.iter()
+            .flat_map(|row| row.current_x.iter().chain(row.baseline_x.iter())),
+    );
+    let y_range = -0.5..rows.len() as f64 - 0.5;
+
+    let size = (960, 150 + (18 * rows.len() as u32));
+
+    let root_area = SVGBackend::new(&path, size)
+        .into_drawing_area()
+        .titled(
+            &format!("{}: Violin plot (baseline vs. current)", title),
+            (DEFAULT_FONT, 20),
+        )
+        .unwrap();
+
+    match axis_scale {
+        AxisScale::Linear => {
+            draw_violin_comparison_figure(root_area, &unit, x_range, y_range, rows)
+        }
+        AxisScale::Logarithmic => {
+            draw_violin_comparison_figure(root_area, &unit, LogRange(x_range), y_range, rows)
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn draw_violin_comparison_figure<XR: AsRangedCoord<Value = f64>, YR: AsRangedCoord<Value = f64>>(
+    root_area: DrawingArea<SVGBackend, Shift>,
+    unit: &str,
+    x_range: XR,
+    y_range: YR,
+    data: Vec<ComparisonRow>,
+) {
+    let mut chart = ChartBuilder::on(&root_area)
+        .margin((5).percent())
+        .set_label_area_size(LabelAreaPosition::Left, (10).percent_width().min(60))
+        .set_label_area_size(LabelAreaPosition::Bottom, (5).percent_width().min(40))
+        .build_ranged(x_range, y_range)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .y_desc("Input")
+        .x_desc(format!("Average time ({})", unit))
+        .y_label_style((DEFAULT_FONT, 10))
+        .y_label_formatter(&|v: &f64| data[v.round() as usize].name.to_string())
+        .y_labels(data.len())
+        .draw()
+        .unwrap();
+
+    for (i, row) in data.into_iter().enumerate() {
+        let base = i as f64;
+
+        chart
+            .draw_series(AreaSeries::new(
+                row.baseline_x
+                    .iter()
+                    .zip(row.baseline_y.iter())
+                    .map(|(x, y)| (*x, base + *y / 2.0)),
+                base,
+                &BASELINE_COLOR.mix(0.25),
+            ))
+            .unwrap();
+        chart
+            .draw_series(AreaSeries::new(
+                row.baseline_x
+                    .iter()
+                    .zip(row.baseline_y.iter())
+                    .map(|(x, y)| (*x, base - *y / 2.0)),
+                base,
+                &BASELINE_COLOR.mix(0.25),
+            ))
+            .unwrap();
+
+        chart
+            .draw_series(AreaSeries::new(
+                row.current_x
+                    .iter()
+                    .zip(row.current_y.iter())
+                    .map(|(x, y)| (*x, base + *y / 2.0)),
+                base,
+                &DARK_BLUE.mix(0.25),
+            ))
+            .unwrap();
+        chart
+            .draw_series(AreaSeries::new(
+                row.current_x
+                    .iter()
+                    .zip(row.current_y.iter())
+                    .map(|(x, y)| (*x, base - *y / 2.0)),
+                base,
+                &DARK_BLUE.mix(0.25),
+            ))
+            .unwrap();
+
+        chart
+            .draw_series(LineSeries::new(
+                vec![
+                    (row.baseline_mean, base - 0.45),
+                    (row.baseline_mean, base + 0.45),
+                ],
+                &BASELINE_COLOR,
+            ))
+            .unwrap();
+        chart
+            .draw_series(LineSeries::new(
+                vec![
+                    (row.current_mean, base - 0.45),
+                    (row.current_mean, base + 0.45),
+                ],
+                &DARK_BLUE,
+            ))
+            .unwrap();
+    }
+}